@@ -2,9 +2,11 @@ use bevy::{
     ecs::{
         bundle::Bundle,
         entity::Entity,
-        system::{Commands, EntityCommands},
+        system::{Command, Commands, EntityCommand, EntityCommands},
+        world::EntityWorldMut,
     },
-    hierarchy::BuildChildren,
+    hierarchy::{BuildChildren, BuildWorldChildren},
+    log::warn,
     prelude::*,
 };
 use bevy::ecs::system::IntoObserverSystem;
@@ -22,9 +24,108 @@ pub struct UiRoot;
 #[reflect(Component)]
 pub struct UiContextRoot;
 
+/// Object-safe stand-in for [`Bundle`] so heterogeneous bundles (e.g. loaded
+/// from serialized scenes/config/mods) can be collected as
+/// `Box<dyn ApplicableBundle>` and applied to an entity later.
+pub trait ApplicableBundle: Send + Sync + 'static {
+    fn apply(self: Box<Self>, entity: &mut EntityWorldMut);
+}
+
+impl<B: Bundle> ApplicableBundle for B {
+    fn apply(self: Box<Self>, entity: &mut EntityWorldMut) {
+        entity.insert(*self);
+    }
+}
+
+struct ApplyBoxedBundle(Box<dyn ApplicableBundle>);
+
+impl EntityCommand for ApplyBoxedBundle {
+    fn apply(self, id: Entity, world: &mut World) {
+        let mut entity = world.entity_mut(id);
+        self.0.apply(&mut entity);
+    }
+}
+
+struct SpawnSibling {
+    source: Entity,
+    known_parent: Option<Entity>,
+    child: Entity,
+}
+
+impl Command for SpawnSibling {
+    fn apply(self, world: &mut World) {
+        let parent = self
+            .known_parent
+            .or_else(|| world.get::<Parent>(self.source).map(Parent::get));
+
+        if let Some(parent) = parent {
+            world.entity_mut(parent).add_child(self.child);
+        } else {
+            warn!(
+                "then_sibling: entity {:?} has no parent, leaving its sibling {:?} un-parented",
+                self.source, self.child
+            );
+        }
+    }
+}
+
+struct SpawnInContextRoot {
+    source: Entity,
+    child: Entity,
+}
+
+impl Command for SpawnInContextRoot {
+    fn apply(self, world: &mut World) {
+        let mut root = self.source;
+        let mut current = self.source;
+        let mut found_context_root = false;
+        while let Some(parent) = world.get::<Parent>(current) {
+            current = parent.get();
+            root = current;
+            if world.get::<UiContextRoot>(current).is_some() {
+                found_context_root = true;
+                break;
+            }
+        }
+
+        if !found_context_root {
+            warn!(
+                "spawn_in_context_root: no UiContextRoot ancestor above {:?}, falling back to topmost root {:?}",
+                self.source, root
+            );
+        }
+
+        let Some(mut root_entity) = world.get_entity_mut(root) else {
+            warn!(
+                "spawn_in_context_root: root entity {:?} no longer exists, leaving {:?} un-parented",
+                root, self.child
+            );
+            return;
+        };
+        root_entity.add_child(self.child);
+    }
+}
+
+/// A forward reference to an entity that hasn't been spawned yet, obtained
+/// via [`UiBuilder::reserve_handle`].
+///
+/// Lets UI construction code reference an entity before its bundle exists
+/// (e.g. a label's `for` target, a scroll view's viewport, tab buttons
+/// pointing at content panes) without manually threading `Entity::PLACEHOLDER`
+/// and post-patching it later.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct UiHandle(Entity);
+
+impl UiHandle {
+    pub fn entity(&self) -> Entity {
+        self.0
+    }
+}
+
 pub struct UiBuilder<'a, T> {
     commands: Commands<'a, 'a>,
     context: T,
+    parent: Option<Entity>,
 }
 
 impl<'a, T> UiBuilder<'a, T> {
@@ -35,6 +136,22 @@ impl<'a, T> UiBuilder<'a, T> {
     pub fn commands(&mut self) -> &mut Commands<'a, 'a> {
         &mut self.commands
     }
+
+    /// Reserves an entity up front so it can be referenced as a [`UiHandle`] before it's spawned.
+    pub fn reserve_handle(&mut self) -> UiHandle {
+        UiHandle(self.commands().spawn_empty().id())
+    }
+}
+
+impl<'a, T: Clone> UiBuilder<'a, T> {
+    /// Reborrows this builder with a shortened lifetime.
+    pub fn reborrow(&mut self) -> UiBuilder<'_, T> {
+        UiBuilder {
+            commands: self.commands.reborrow(),
+            context: self.context.clone(),
+            parent: self.parent,
+        }
+    }
 }
 
 impl UiBuilder<'_, UiRoot> {
@@ -43,6 +160,20 @@ impl UiBuilder<'_, UiRoot> {
 
         self.commands().ui_builder(new_entity)
     }
+
+    pub fn spawn_dyn(&mut self, bundle: Box<dyn ApplicableBundle>) -> UiBuilder<Entity> {
+        let new_entity = self.commands().spawn_empty().id();
+        self.commands().entity(new_entity).add(ApplyBoxedBundle(bundle));
+
+        self.commands().ui_builder(new_entity)
+    }
+
+    /// Fills a handle previously reserved via [`UiBuilder::reserve_handle`] with its real bundle.
+    pub fn spawn_reserved(&mut self, handle: UiHandle, bundle: impl Bundle) -> UiBuilder<Entity> {
+        self.commands().entity(handle.entity()).insert(bundle);
+
+        self.commands().ui_builder(handle.entity())
+    }
 }
 
 impl UiBuilder<'_, Entity> {
@@ -95,9 +226,80 @@ impl UiBuilder<'_, Entity> {
             new_entity = parent.spawn(bundle).id();
         });
 
+        let mut builder = self.commands().ui_builder(new_entity);
+        builder.parent = Some(entity);
+        builder
+    }
+
+    /// Spawns `bundle` as a child of the nearest ancestor carrying [`UiContextRoot`].
+    pub fn spawn_in_context_root(&mut self, bundle: impl Bundle) -> UiBuilder<Entity> {
+        let source = self.id();
+        let new_entity = self.commands().spawn(bundle).id();
+
+        self.commands().add(SpawnInContextRoot {
+            source,
+            child: new_entity,
+        });
+
         self.commands().ui_builder(new_entity)
     }
 
+    /// Fills a handle previously reserved via [`UiBuilder::reserve_handle`] with its real bundle.
+    pub fn spawn_reserved(&mut self, handle: UiHandle, bundle: impl Bundle) -> UiBuilder<Entity> {
+        let entity = self.id();
+        self.commands().entity(entity).add_child(handle.entity());
+        self.commands().entity(handle.entity()).insert(bundle);
+
+        let mut builder = self.commands().ui_builder(handle.entity());
+        builder.parent = Some(entity);
+        builder
+    }
+
+    pub fn spawn_dyn(&mut self, bundle: Box<dyn ApplicableBundle>) -> UiBuilder<Entity> {
+        let mut new_entity = Entity::PLACEHOLDER;
+
+        let entity = self.id();
+        self.commands().entity(entity).with_children(|parent| {
+            new_entity = parent.spawn_empty().id();
+        });
+        self.commands().entity(new_entity).add(ApplyBoxedBundle(bundle));
+
+        let mut builder = self.commands().ui_builder(new_entity);
+        builder.parent = Some(entity);
+        builder
+    }
+
+    /// Spawns many children of the current entity in one `with_children` call and returns their ids.
+    pub fn spawn_siblings(&mut self, bundles: impl IntoIterator<Item = impl Bundle>) -> Vec<Entity> {
+        let mut new_entities = Vec::new();
+
+        let entity = self.id();
+        self.commands().entity(entity).with_children(|parent| {
+            for bundle in bundles {
+                new_entities.push(parent.spawn(bundle).id());
+            }
+        });
+
+        new_entities
+    }
+
+    /// Spawns a new child of the same parent as this builder's current entity.
+    pub fn then_sibling(&mut self, bundle: impl Bundle) -> UiBuilder<Entity> {
+        let source = self.id();
+        let known_parent = self.parent;
+        let new_entity = self.commands().spawn(bundle).id();
+
+        self.commands().add(SpawnSibling {
+            source,
+            known_parent,
+            child: new_entity,
+        });
+
+        let mut builder = self.commands().ui_builder(new_entity);
+        builder.parent = known_parent;
+        builder
+    }
+
     pub fn insert(&mut self, bundle: impl Bundle) -> &mut Self {
         self.entity_commands().insert(bundle);
         self
@@ -126,6 +328,9 @@ impl UiBuilder<'_, Entity> {
 
 pub trait UiBuilderExt {
     fn ui_builder<T>(&mut self, context: T) -> UiBuilder<T>;
+
+    /// Starts building at the entity reserved by a [`UiHandle`].
+    fn ui_builder_at(&mut self, handle: UiHandle) -> UiBuilder<Entity>;
 }
 
 impl UiBuilderExt for Commands<'_, '_> {
@@ -133,6 +338,50 @@ impl UiBuilderExt for Commands<'_, '_> {
         UiBuilder {
             commands: self.reborrow(),
             context,
+            parent: None,
         }
     }
+
+    fn ui_builder_at(&mut self, handle: UiHandle) -> UiBuilder<Entity> {
+        self.ui_builder(handle.entity())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::CommandQueue;
+
+    use super::*;
+
+    #[test]
+    fn then_sibling_shares_parent_with_spawn() {
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+
+        let mut commands = Commands::new(&mut queue, &world);
+        let row = commands.spawn_empty().id();
+        let mut row_builder = commands.ui_builder(row);
+        let mut a_builder = row_builder.spawn(());
+        let a = a_builder.id();
+        let b = a_builder.then_sibling(()).id();
+        queue.apply(&mut world);
+
+        assert_eq!(world.get::<Parent>(a).map(Parent::get), Some(row));
+        assert_eq!(world.get::<Parent>(b).map(Parent::get), Some(row));
+    }
+
+    #[test]
+    fn spawn_in_context_root_escapes_to_nearest_context_root() {
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+
+        let mut commands = Commands::new(&mut queue, &world);
+        let context_root = commands.spawn(UiContextRoot).id();
+        let trigger = commands.spawn_empty().id();
+        commands.entity(trigger).set_parent(context_root);
+        let popup = commands.ui_builder(trigger).spawn_in_context_root(()).id();
+        queue.apply(&mut world);
+
+        assert_eq!(world.get::<Parent>(popup).map(Parent::get), Some(context_root));
+    }
 }